@@ -4,11 +4,41 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Schema version written by this binary. Bump it together with a new entry
+/// in [`MIGRATIONS`] whenever the document layout changes incompatibly.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// Upgrade step applied to a raw document at the given `config_version`. Each
+/// step leaves the document at the next version.
+type Migration = fn(&mut serde_yaml::Value);
+
+const MIGRATIONS: &[(u32, Migration)] = &[];
+
+fn current_config_version() -> u32 {
+    CURRENT_CONFIG_VERSION
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
+    #[serde(default = "current_config_version")]
+    pub config_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<TagRegistry>,
     pub system: SystemTopology,
 }
 
+/// Known sensor tags that controller `sensor_inputs` may reference. When the
+/// registry is omitted, sensor inputs are not checked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagRegistry(pub Vec<String>);
+
+impl TagRegistry {
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|known| known == tag)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemTopology {
     pub grids: Vec<GridConfig>,
@@ -26,7 +56,7 @@ pub struct GridConfig {
     pub allow_interop: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerRole {
     Primary,
@@ -40,7 +70,7 @@ impl Default for ControllerRole {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ControllerConfig {
     pub id: String,
     #[serde(default)]
@@ -53,6 +83,8 @@ pub struct ControllerConfig {
     pub failover_timeout_ms: Option<u64>,
     #[serde(default)]
     pub sync_channels: Vec<String>,
+    #[serde(default)]
+    pub sensor_inputs: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +140,13 @@ pub struct ValidationReport {
     pub devices: usize,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigDiff {
+    pub added_grids: Vec<String>,
+    pub removed_grids: Vec<String>,
+    pub modified_controllers: Vec<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("failed to read configuration from {path:?}")]
@@ -122,23 +161,238 @@ pub enum ConfigError {
         #[source]
         source: serde_yaml::Error,
     },
+    #[error("unresolved environment variable '{variable}' for key '{key}' at {path:?}")]
+    Interpolation {
+        path: PathBuf,
+        key: String,
+        variable: String,
+    },
+    #[error("environment variable '{variable}' for key '{key}' at {path:?} is not valid UTF-8")]
+    InterpolationEncoding {
+        path: PathBuf,
+        key: String,
+        variable: String,
+    },
+    #[error(
+        "configuration at {path:?} has config_version {found}, but this binary only \
+         supports up to {supported}; upgrade the binary"
+    )]
+    UnsupportedVersion {
+        path: PathBuf,
+        found: u32,
+        supported: u32,
+    },
+    #[error(
+        "failed to parse configuration at {path:?}: config_version must be a non-negative integer"
+    )]
+    InvalidVersion { path: PathBuf },
+    #[error("configuration at {path:?} has config_version {found} with no migration path")]
+    MissingMigration { path: PathBuf, found: u32 },
     #[error("configuration validation failed:\n{details}")]
     Validation { details: String },
 }
 
-pub fn load_config(path: impl AsRef<Path>) -> Result<SystemConfig, ConfigError> {
+/// A configuration as written, with `${VAR}` references intact, and as
+/// resolved against the process environment.
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub template: SystemConfig,
+    pub config: SystemConfig,
+}
+
+pub fn load_config(path: impl AsRef<Path>) -> Result<LoadedConfig, ConfigError> {
     let path = path.as_ref();
     let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
         path: path.to_path_buf(),
         source,
     })?;
 
-    serde_yaml::from_str(&contents).map_err(|source| ConfigError::Parse {
+    parse_config(path, &contents)
+}
+
+fn parse_config(path: &Path, contents: &str) -> Result<LoadedConfig, ConfigError> {
+    let document = serde_yaml::from_str(contents).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    config_from_document(path, document)
+}
+
+/// Resolves environment references, applies version migration, and
+/// deserializes a parsed document. Used for files on disk and for candidates
+/// submitted over HTTP alike.
+pub fn config_from_document(
+    path: &Path,
+    document: serde_yaml::Value,
+) -> Result<LoadedConfig, ConfigError> {
+    config_from_document_with(path, document, MIGRATIONS, CURRENT_CONFIG_VERSION)
+}
+
+fn config_from_document_with(
+    path: &Path,
+    document: serde_yaml::Value,
+    migrations: &[(u32, Migration)],
+    current: u32,
+) -> Result<LoadedConfig, ConfigError> {
+    let mut resolved = document.clone();
+    interpolate_env(path, &mut resolved, "")?;
+
+    Ok(LoadedConfig {
+        template: deserialize(path, migrate_with(path, document, migrations, current)?)?,
+        config: deserialize(path, migrate_with(path, resolved, migrations, current)?)?,
+    })
+}
+
+fn deserialize(path: &Path, document: serde_yaml::Value) -> Result<SystemConfig, ConfigError> {
+    serde_yaml::from_value(document).map_err(|source| ConfigError::Parse {
         path: path.to_path_buf(),
         source,
     })
 }
 
+/// Upgrades a raw document to `current` by chaining the registered
+/// migrations. Documents without `config_version` are treated as current.
+fn migrate_with(
+    path: &Path,
+    mut document: serde_yaml::Value,
+    migrations: &[(u32, Migration)],
+    current: u32,
+) -> Result<serde_yaml::Value, ConfigError> {
+    let version = match document.get("config_version") {
+        None => current,
+        Some(value) => value
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| ConfigError::InvalidVersion {
+                path: path.to_path_buf(),
+            })?,
+    };
+
+    if version > current {
+        return Err(ConfigError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            found: version,
+            supported: current,
+        });
+    }
+
+    for from in version..current {
+        let (_, step) = migrations
+            .iter()
+            .find(|(source, _)| *source == from)
+            .ok_or_else(|| ConfigError::MissingMigration {
+                path: path.to_path_buf(),
+                found: from,
+            })?;
+        step(&mut document);
+    }
+
+    if let Some(mapping) = document.as_mapping_mut() {
+        mapping.insert("config_version".into(), current.into());
+    }
+
+    Ok(document)
+}
+
+/// Substitutes `${VAR}` and `${VAR:-default}` references inside string values
+/// with values from the process environment. As in the shell, `:-` also
+/// applies the default when the variable is set but empty. `$${` produces a
+/// literal `${`.
+/// Keys, comments, and non-string scalars are left untouched, so substituted
+/// values can never change the structure of the document.
+fn interpolate_env(
+    path: &Path,
+    value: &mut serde_yaml::Value,
+    key: &str,
+) -> Result<(), ConfigError> {
+    match value {
+        serde_yaml::Value::String(text) => {
+            if text.contains('$') {
+                *text = interpolate_str(text).map_err(|(variable, failure)| match failure {
+                    std::env::VarError::NotPresent => ConfigError::Interpolation {
+                        path: path.to_path_buf(),
+                        key: key.to_string(),
+                        variable,
+                    },
+                    std::env::VarError::NotUnicode(_) => ConfigError::InterpolationEncoding {
+                        path: path.to_path_buf(),
+                        key: key.to_string(),
+                        variable,
+                    },
+                })?;
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                interpolate_env(path, item, &format!("{key}[{index}]"))?;
+            }
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            for (name, item) in mapping.iter_mut() {
+                let name = match name {
+                    serde_yaml::Value::String(name) => name.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|name| name.trim_end().to_string())
+                        .unwrap_or_default(),
+                };
+                let child = if key.is_empty() {
+                    name
+                } else {
+                    format!("{key}.{name}")
+                };
+                interpolate_env(path, item, &child)?;
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => interpolate_env(path, &mut tagged.value, key)?,
+        serde_yaml::Value::Null | serde_yaml::Value::Bool(_) | serde_yaml::Value::Number(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Expands the references in a single string. On failure returns the variable
+/// name together with the lookup error.
+fn interpolate_str(text: &str) -> Result<String, (String, std::env::VarError)> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+
+        let Some(len) = rest.strip_prefix("${").and_then(|body| body.find('}')) else {
+            output.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let expression = &rest[2..2 + len];
+        let (variable, default) = match expression.split_once(":-") {
+            Some((variable, default)) => (variable.trim(), Some(default)),
+            None => (expression.trim(), None),
+        };
+
+        match (std::env::var(variable), default) {
+            (Ok(value), Some(default)) if value.is_empty() => output.push_str(default),
+            (Ok(value), _) => output.push_str(&value),
+            (Err(std::env::VarError::NotPresent), Some(default)) => output.push_str(default),
+            (Err(err), _) => return Err((variable.to_string(), err)),
+        }
+
+        rest = &rest[2 + len + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
 pub fn validate_config(config: &SystemConfig) -> Result<ValidationReport, ConfigError> {
     let mut errors = Vec::new();
 
@@ -181,6 +435,29 @@ pub fn validate_config(config: &SystemConfig) -> Result<ValidationReport, Config
                 ));
             }
 
+            if let Some(registry) = &config.tags {
+                for tag in &controller.sensor_inputs {
+                    if !registry.contains(tag) {
+                        errors.push(format!(
+                            "controller '{}' in grid '{}' references unknown sensor input tag '{}'",
+                            controller.id, grid.id, tag
+                        ));
+                    }
+                }
+            }
+
+            if let (Some(heartbeat), Some(timeout)) = (
+                controller.heartbeat_interval_ms,
+                controller.failover_timeout_ms,
+            ) {
+                if timeout < heartbeat.saturating_mul(2) {
+                    errors.push(format!(
+                        "controller '{}' in grid '{}' has failover_timeout_ms {} shorter than twice heartbeat_interval_ms {}",
+                        controller.id, grid.id, timeout, heartbeat
+                    ));
+                }
+            }
+
             match controller.role {
                 ControllerRole::Primary | ControllerRole::Backup => {
                     let group = controller.redundancy_group.clone().unwrap_or_else(|| {
@@ -349,3 +626,421 @@ pub fn validate_config(config: &SystemConfig) -> Result<ValidationReport, Config
         })
     }
 }
+
+/// Compares a candidate configuration against the one currently served.
+///
+/// Controllers are reported as `grid_id/controller_id` when they were added,
+/// removed, or changed inside a grid present in both documents.
+pub fn diff_config(current: &SystemConfig, candidate: &SystemConfig) -> ConfigDiff {
+    let current_grids: HashMap<&str, &GridConfig> = current
+        .system
+        .grids
+        .iter()
+        .map(|grid| (grid.id.as_str(), grid))
+        .collect();
+    let candidate_grids: HashMap<&str, &GridConfig> = candidate
+        .system
+        .grids
+        .iter()
+        .map(|grid| (grid.id.as_str(), grid))
+        .collect();
+
+    let mut diff = ConfigDiff::default();
+
+    for grid in &candidate.system.grids {
+        if !current_grids.contains_key(grid.id.as_str()) {
+            diff.added_grids.push(grid.id.clone());
+        }
+    }
+
+    for grid in &current.system.grids {
+        let Some(next) = candidate_grids.get(grid.id.as_str()) else {
+            diff.removed_grids.push(grid.id.clone());
+            continue;
+        };
+
+        let before: HashMap<&str, &ControllerConfig> = grid
+            .controllers
+            .iter()
+            .map(|controller| (controller.id.as_str(), controller))
+            .collect();
+        let after: HashMap<&str, &ControllerConfig> = next
+            .controllers
+            .iter()
+            .map(|controller| (controller.id.as_str(), controller))
+            .collect();
+
+        let mut changed: Vec<&str> = before
+            .iter()
+            .filter(|(id, controller)| after.get(*id) != Some(*controller))
+            .map(|(id, _)| *id)
+            .chain(after.keys().copied().filter(|id| !before.contains_key(id)))
+            .collect();
+        changed.sort_unstable();
+
+        diff.modified_controllers.extend(
+            changed
+                .into_iter()
+                .map(|controller| format!("{}/{}", grid.id, controller)),
+        );
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+system:
+  grids:
+    - id: grid_a
+      controllers:
+        - id: grid_a_ctrl_primary
+          role: primary
+          redundancy_group: grid_a_cluster
+          heartbeat_interval_ms: 500
+          failover_timeout_ms: 1500
+        - id: grid_a_ctrl_backup
+          role: backup
+          redundancy_group: grid_a_cluster
+          heartbeat_interval_ms: 500
+          failover_timeout_ms: 1500
+      devices:
+        - id: inverter_main
+          bus: can
+          address: "0x12"
+          protocol:
+            dbc_file: dbcs/inverter.dbc
+          telemetry:
+            - name: voltage
+    - id: grid_b
+      controllers:
+        - id: grid_b_ctrl_standalone
+      devices:
+        - id: relay_pump
+          bus: rs485
+          address: "15"
+          protocol:
+            register_map: registers/relay.toml
+          telemetry:
+            - name: state
+"#;
+
+    fn parse(contents: &str) -> Result<SystemConfig, ConfigError> {
+        parse_config(Path::new("test.yaml"), contents).map(|loaded| loaded.config)
+    }
+
+    fn base() -> SystemConfig {
+        parse(BASE).expect("base fixture parses")
+    }
+
+    #[test]
+    fn base_fixture_is_valid() {
+        validate_config(&base()).unwrap();
+    }
+
+    fn diff_against_base(edit: impl FnOnce(&mut SystemConfig)) -> ConfigDiff {
+        let current = base();
+        let mut candidate = current.clone();
+        edit(&mut candidate);
+        diff_config(&current, &candidate)
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_grids() {
+        let diff = diff_against_base(|candidate| {
+            let mut grid = candidate.system.grids.remove(1);
+            grid.id = "grid_c".to_string();
+            candidate.system.grids.push(grid);
+        });
+
+        assert_eq!(diff.added_grids, vec!["grid_c"]);
+        assert_eq!(diff.removed_grids, vec!["grid_b"]);
+        assert!(diff.modified_controllers.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_controllers() {
+        let diff = diff_against_base(|candidate| {
+            let controllers = &mut candidate.system.grids[0].controllers;
+            controllers[0].failover_timeout_ms = Some(2000);
+            let mut added = controllers.remove(1);
+            added.id = "grid_a_ctrl_spare".to_string();
+            controllers.push(added);
+        });
+
+        assert!(diff.added_grids.is_empty());
+        assert!(diff.removed_grids.is_empty());
+        assert_eq!(
+            diff.modified_controllers,
+            vec![
+                "grid_a/grid_a_ctrl_backup",
+                "grid_a/grid_a_ctrl_primary",
+                "grid_a/grid_a_ctrl_spare",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_is_empty_for_identical_configs() {
+        let diff = diff_against_base(|_| {});
+
+        assert!(diff.added_grids.is_empty());
+        assert!(diff.removed_grids.is_empty());
+        assert!(diff.modified_controllers.is_empty());
+    }
+
+    fn with_address(address: &str) -> String {
+        BASE.replace("\"0x12\"", address)
+    }
+
+    #[test]
+    fn interpolation_substitutes_set_variables() {
+        std::env::set_var("REMS_TEST_INTERP_SET", "0x34");
+        let config = parse(&with_address("\"${REMS_TEST_INTERP_SET}\"")).unwrap();
+        assert_eq!(config.system.grids[0].devices[0].address, "0x34");
+    }
+
+    #[test]
+    fn interpolation_falls_back_to_default() {
+        let config = parse(&with_address("\"${REMS_TEST_INTERP_UNSET:-0x56}\"")).unwrap();
+        assert_eq!(config.system.grids[0].devices[0].address, "0x56");
+    }
+
+    #[test]
+    fn interpolation_uses_default_for_empty_variables() {
+        std::env::set_var("REMS_TEST_INTERP_EMPTY", "");
+        let config = parse(&with_address("\"${REMS_TEST_INTERP_EMPTY:-0x78}\"")).unwrap();
+        assert_eq!(config.system.grids[0].devices[0].address, "0x78");
+    }
+
+    #[test]
+    fn interpolation_reports_missing_variable_with_key() {
+        let err = parse(&with_address("\"${REMS_TEST_INTERP_MISSING}\"")).unwrap_err();
+        match err {
+            ConfigError::Interpolation { key, variable, .. } => {
+                assert_eq!(key, "system.grids[0].devices[0].address");
+                assert_eq!(variable, "REMS_TEST_INTERP_MISSING");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn interpolation_ignores_comments() {
+        let contents = format!("# address: \"${{REMS_TEST_INTERP_COMMENTED}}\"\n{BASE}");
+        assert!(parse(&contents).is_ok());
+    }
+
+    #[test]
+    fn interpolation_keeps_special_characters_inside_the_value() {
+        std::env::set_var("REMS_TEST_INTERP_SPECIAL", "0x12 #x: y\nz");
+        let config = parse(&with_address("${REMS_TEST_INTERP_SPECIAL}")).unwrap();
+        assert_eq!(config.system.grids[0].devices[0].address, "0x12 #x: y\nz");
+    }
+
+    #[test]
+    fn interpolation_supports_escaping() {
+        let config = parse(&with_address("\"$${NOT_A_VAR}\"")).unwrap();
+        assert_eq!(config.system.grids[0].devices[0].address, "${NOT_A_VAR}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn interpolation_rejects_non_unicode_values() {
+        use std::os::unix::ffi::OsStrExt;
+
+        std::env::set_var(
+            "REMS_TEST_INTERP_NOT_UNICODE",
+            std::ffi::OsStr::from_bytes(&[0x66, 0xff]),
+        );
+        let err = parse(&with_address(
+            "\"${REMS_TEST_INTERP_NOT_UNICODE:-fallback}\"",
+        ))
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::InterpolationEncoding { .. }));
+    }
+
+    #[test]
+    fn template_keeps_references_as_written() {
+        let loaded = parse_config(
+            Path::new("test.yaml"),
+            &with_address("\"${REMS_TEST_INTERP_TEMPLATE:-0x9a}\""),
+        )
+        .unwrap();
+        assert_eq!(loaded.config.system.grids[0].devices[0].address, "0x9a");
+        assert_eq!(
+            loaded.template.system.grids[0].devices[0].address,
+            "${REMS_TEST_INTERP_TEMPLATE:-0x9a}"
+        );
+    }
+
+    /// Hypothetical v1 layout that listed controllers under `controller_list`.
+    fn rename_controller_list(document: &mut serde_yaml::Value) {
+        for grid in document["system"]["grids"].as_sequence_mut().unwrap() {
+            if let Some(grid) = grid.as_mapping_mut() {
+                if let Some(controllers) = grid.remove("controller_list") {
+                    grid.insert("controllers".into(), controllers);
+                }
+            }
+        }
+    }
+
+    /// Hypothetical v2 layout where `name` was optional and defaults to the id.
+    fn default_grid_name(document: &mut serde_yaml::Value) {
+        for grid in document["system"]["grids"].as_sequence_mut().unwrap() {
+            if let Some(grid) = grid.as_mapping_mut() {
+                let id = grid.get("id").cloned().unwrap_or_default();
+                grid.entry("name".into()).or_insert(id);
+            }
+        }
+    }
+
+    const TEST_MIGRATIONS: &[(u32, Migration)] =
+        &[(1, rename_controller_list), (2, default_grid_name)];
+
+    const V1_DOCUMENT: &str = r#"
+config_version: 1
+system:
+  grids:
+    - id: grid_a
+      controller_list:
+        - id: grid_a_ctrl
+"#;
+
+    #[test]
+    fn migration_chains_steps_to_current_version() {
+        let document = serde_yaml::from_str(V1_DOCUMENT).unwrap();
+        let migrated = migrate_with(Path::new("test.yaml"), document, TEST_MIGRATIONS, 3).unwrap();
+
+        assert_eq!(migrated["config_version"], serde_yaml::Value::from(3));
+        let grid = &migrated["system"]["grids"][0];
+        assert_eq!(
+            grid["controllers"][0]["id"],
+            serde_yaml::Value::from("grid_a_ctrl")
+        );
+        assert!(grid.get("controller_list").is_none());
+        assert_eq!(grid["name"], serde_yaml::Value::from("grid_a"));
+    }
+
+    #[test]
+    fn migrated_document_deserializes_with_defaulted_field() {
+        let document = serde_yaml::from_str(V1_DOCUMENT).unwrap();
+        let loaded =
+            config_from_document_with(Path::new("test.yaml"), document, TEST_MIGRATIONS, 3)
+                .unwrap();
+
+        let config = loaded.config;
+        assert_eq!(config.config_version, 3);
+        assert_eq!(config.system.grids[0].name.as_deref(), Some("grid_a"));
+        assert_eq!(config.system.grids[0].controllers[0].id, "grid_a_ctrl");
+    }
+
+    #[test]
+    fn migration_rejects_gaps_in_the_chain() {
+        let document = serde_yaml::from_str(V1_DOCUMENT).unwrap();
+        let err = migrate_with(
+            Path::new("test.yaml"),
+            document,
+            &[(2, default_grid_name)],
+            3,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::MissingMigration { found: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn migration_rejects_newer_versions() {
+        let err = parse(&format!(
+            "config_version: {}\n{BASE}",
+            CURRENT_CONFIG_VERSION + 1
+        ))
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::UnsupportedVersion { .. }));
+    }
+
+    #[test]
+    fn migration_rejects_non_integer_versions() {
+        let err = parse(&format!("config_version: one\n{BASE}")).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidVersion { .. }));
+    }
+
+    #[test]
+    fn missing_version_defaults_to_current() {
+        assert_eq!(base().config_version, CURRENT_CONFIG_VERSION);
+    }
+
+    fn with_sensor_inputs(registry: &[&str], inputs: &[&str]) -> SystemConfig {
+        let mut config = base();
+        config.tags = Some(TagRegistry(
+            registry.iter().map(|tag| tag.to_string()).collect(),
+        ));
+        config.system.grids[0].controllers[0].sensor_inputs =
+            inputs.iter().map(|tag| tag.to_string()).collect();
+        config
+    }
+
+    #[test]
+    fn sensor_inputs_pass_when_registered() {
+        let config = with_sensor_inputs(
+            &["inverter_main.voltage", "relay_pump.state"],
+            &["inverter_main.voltage"],
+        );
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    fn sensor_inputs_fail_on_unknown_tag() {
+        let config = with_sensor_inputs(&["inverter_main.voltage"], &["inverter_main.volts"]);
+
+        match validate_config(&config).unwrap_err() {
+            ConfigError::Validation { details } => assert_eq!(
+                details,
+                "controller 'grid_a_ctrl_primary' in grid 'grid_a' references unknown sensor input tag 'inverter_main.volts'"
+            ),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn sensor_inputs_are_unchecked_without_registry() {
+        let mut config = base();
+        config.system.grids[0].controllers[0].sensor_inputs = vec!["anything".to_string()];
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    fn absent_tag_registry_is_not_serialized() {
+        let json = serde_json::to_value(base()).unwrap();
+        assert!(json.get("tags").is_none());
+    }
+
+    fn with_timers(heartbeat_ms: u64, failover_ms: u64) -> SystemConfig {
+        let mut config = base();
+        for controller in &mut config.system.grids[0].controllers {
+            controller.heartbeat_interval_ms = Some(heartbeat_ms);
+            controller.failover_timeout_ms = Some(failover_ms);
+        }
+        config
+    }
+
+    #[test]
+    fn failover_timeout_below_twice_heartbeat_fails() {
+        match validate_config(&with_timers(500, 900)).unwrap_err() {
+            ConfigError::Validation { details } => assert!(details.contains(
+                "controller 'grid_a_ctrl_primary' in grid 'grid_a' has failover_timeout_ms 900 shorter than twice heartbeat_interval_ms 500"
+            )),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn failover_timeout_of_exactly_twice_heartbeat_passes() {
+        validate_config(&with_timers(500, 1000)).unwrap();
+    }
+}
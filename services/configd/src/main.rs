@@ -4,9 +4,17 @@ use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use axum::{extract::State, routing::get, Json, Router};
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
 use clap::{Parser, Subcommand};
-use config::{load_config, validate_config, SystemConfig, ValidationReport};
+use config::{
+    config_from_document, diff_config, load_config, validate_config, ConfigDiff, ConfigError,
+    SystemConfig, ValidationReport,
+};
 use serde::Serialize;
 use tokio::{net::TcpListener, signal};
 use tracing::{info, warn};
@@ -57,6 +65,9 @@ impl Default for Command {
 #[derive(Clone)]
 struct AppState {
     config: Arc<SystemConfig>,
+    /// The served configuration as written, with `${VAR}` references intact,
+    /// so dry-run diffs are not skewed by environment interpolation.
+    template: Arc<SystemConfig>,
     summary: ValidationReport,
 }
 
@@ -67,6 +78,17 @@ struct SummaryResponse {
     devices: usize,
 }
 
+#[derive(Serialize)]
+struct DryRunResponse {
+    summary: ValidationReport,
+    diff: ConfigDiff,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -77,7 +99,7 @@ async fn main() -> anyhow::Result<()> {
     match command {
         Command::Validate => {
             info!(path = %cli.config.display(), "validating configuration");
-            let config = load_config(&cli.config)?;
+            let config = load_config(&cli.config)?.config;
             let summary = validate_config(&config)?;
             info!(
                 grids = summary.grids,
@@ -92,7 +114,8 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    let config = Arc::new(load_config(&cli.config)?);
+    let loaded = load_config(&cli.config)?;
+    let config = Arc::new(loaded.config);
     let summary = validate_config(&config)?;
 
     info!(
@@ -110,14 +133,11 @@ async fn main() -> anyhow::Result<()> {
 
     let state = AppState {
         config: Arc::clone(&config),
+        template: Arc::new(loaded.template),
         summary: summary.clone(),
     };
 
-    let app = Router::new()
-        .route("/api/config", get(get_config))
-        .route("/api/config/summary", get(get_summary))
-        .route("/healthz", get(|| async { "ok" }))
-        .with_state(state);
+    let app = router(state);
 
     info!(%cli.bind, "starting configd server");
 
@@ -161,6 +181,52 @@ async fn get_summary(State(state): State<AppState>) -> Json<SummaryResponse> {
     })
 }
 
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/api/config", get(get_config))
+        .route("/api/config/summary", get(get_summary))
+        .route("/api/config/validate", post(validate_candidate))
+        .route("/healthz", get(|| async { "ok" }))
+        .with_state(state)
+}
+
+/// Validates a candidate configuration and reports how it differs from the
+/// served snapshot. Nothing is written or applied. The candidate goes through
+/// the same environment interpolation and version migration as a file load;
+/// the diff compares both documents as written, so `${VAR}` references are not
+/// reported as changes.
+async fn validate_candidate(
+    State(state): State<AppState>,
+    payload: Result<Json<serde_yaml::Value>, JsonRejection>,
+) -> Result<Json<DryRunResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+
+    let Json(document) = payload.map_err(|rejection| bad_request(rejection.body_text()))?;
+    let candidate = config_from_document(Path::new("request body"), document)
+        .map_err(|err| bad_request(describe_error(err)))?;
+    let summary =
+        validate_config(&candidate.config).map_err(|err| bad_request(describe_error(err)))?;
+
+    Ok(Json(DryRunResponse {
+        summary,
+        diff: diff_config(&state.template, &candidate.template),
+    }))
+}
+
+fn describe_error(err: ConfigError) -> String {
+    if let ConfigError::Validation { details } = err {
+        return details;
+    }
+
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(&err);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
 async fn shutdown_signal() {
     #[cfg(unix)]
     {
@@ -196,3 +262,165 @@ async fn terminate() {
 
 #[cfg(not(unix))]
 async fn terminate() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{body::Body, http::Request};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    const CURRENT: &str = r#"{
+        "system": {
+            "grids": [
+                {
+                    "id": "grid_a",
+                    "controllers": [{ "id": "grid_a_ctrl" }],
+                    "devices": [{
+                        "id": "inverter_main",
+                        "bus": "can",
+                        "address": "0x12",
+                        "protocol": { "dbc_file": "dbcs/inverter.dbc" },
+                        "telemetry": [{ "name": "voltage" }]
+                    }]
+                },
+                {
+                    "id": "grid_b",
+                    "controllers": [{ "id": "grid_b_ctrl" }],
+                    "devices": [{
+                        "id": "relay_pump",
+                        "bus": "rs485",
+                        "address": "15",
+                        "protocol": { "register_map": "registers/relay.toml" },
+                        "telemetry": [{ "name": "state" }]
+                    }]
+                }
+            ]
+        }
+    }"#;
+
+    fn document(edit: impl FnOnce(&mut serde_json::Value)) -> serde_json::Value {
+        let mut document: serde_json::Value = serde_json::from_str(CURRENT).unwrap();
+        edit(&mut document);
+        document
+    }
+
+    fn app(served: &serde_json::Value) -> Router {
+        let loaded = config_from_document(
+            Path::new("served.yaml"),
+            serde_json::from_value(served.clone()).unwrap(),
+        )
+        .unwrap();
+        let summary = validate_config(&loaded.config).unwrap();
+        router(AppState {
+            config: Arc::new(loaded.config),
+            template: Arc::new(loaded.template),
+            summary,
+        })
+    }
+
+    async fn post_validate(app: Router, body: String) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .oneshot(
+                Request::post("/api/config/validate")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn remove_grid_b(document: &mut serde_json::Value) {
+        document["system"]["grids"].as_array_mut().unwrap().pop();
+    }
+
+    #[tokio::test]
+    async fn dry_run_lists_removed_grid() {
+        let served = document(|_| {});
+        let body = document(remove_grid_b).to_string();
+
+        let (status, json) = post_validate(app(&served), body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["diff"]["removed_grids"], json!(["grid_b"]));
+        assert_eq!(json["summary"]["grids"], 1);
+    }
+
+    #[tokio::test]
+    async fn dry_run_rejects_malformed_body_with_400() {
+        let served = document(|_| {});
+
+        let (status, json) = post_validate(app(&served), r#"{"system":{}}"#.to_string()).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].as_str().unwrap().contains("grids"));
+    }
+
+    #[tokio::test]
+    async fn dry_run_rejects_invalid_config_with_details() {
+        let served = document(|_| {});
+        let body = document(|document| document["system"]["grids"] = json!([])).to_string();
+
+        let (status, json) = post_validate(app(&served), body).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"], "system must define at least one grid");
+    }
+
+    #[tokio::test]
+    async fn dry_run_diffs_documents_as_written() {
+        let with_reference = |document: &mut serde_json::Value| {
+            document["system"]["grids"][0]["controllers"][0]["sync_channels"] =
+                json!(["${REMS_TEST_DRY_RUN_CHANNEL:-status_bus}"]);
+        };
+        let served = document(with_reference);
+        let body = document(|document| {
+            with_reference(document);
+            remove_grid_b(document);
+        })
+        .to_string();
+
+        let (status, json) = post_validate(app(&served), body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["diff"]["removed_grids"], json!(["grid_b"]));
+        assert_eq!(json["diff"]["modified_controllers"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn dry_run_applies_version_rules() {
+        let served = document(|_| {});
+        let body = document(|document| document["config_version"] = 0.into()).to_string();
+
+        let (status, json) = post_validate(app(&served), body).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"]
+            .as_str()
+            .unwrap()
+            .contains("no migration path"));
+    }
+
+    #[tokio::test]
+    async fn dry_run_resolves_references_before_checking_tags() {
+        let with_tagged_input = |document: &mut serde_json::Value| {
+            document["tags"] = json!(["inverter_main.voltage"]);
+            document["system"]["grids"][0]["controllers"][0]["sensor_inputs"] =
+                json!(["${REMS_TEST_DRY_RUN_TAG:-inverter_main.voltage}"]);
+        };
+        let served = document(with_tagged_input);
+        let body = document(with_tagged_input).to_string();
+
+        let (status, json) = post_validate(app(&served), body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["diff"]["modified_controllers"], json!([]));
+    }
+}